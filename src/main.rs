@@ -1,6 +1,8 @@
-use chrono::{Date, DateTime, Local, NaiveDate, Utc};
+use chrono::{
+    Date, DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc,
+};
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Display};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::{error, result};
@@ -21,24 +23,148 @@ enum Opt {
         length: u32,
     },
 
+    /// Start tracking a meeting that is happening right now
+    ///
+    /// Writes an open record with the current timestamp. Use `stop` once the meeting
+    /// ends to close the record and compute its length.
+    #[structopt(name = "start", about = "Start tracking a live meeting")]
+    Start {
+        /// An optional label for the meeting
+        label: Option<String>,
+    },
+
+    /// Stop tracking the currently open meeting
+    ///
+    /// Finds the most recently opened meeting with no end time, stamps it with the
+    /// current time, and rewrites the log with its computed length.
+    #[structopt(name = "stop", about = "Stop tracking the live meeting")]
+    Stop,
+
     /// List meetings within a given range
     ///
-    /// Provide a start and end date to list all meetings occurring within that date range.
-    /// A single date will list only meetings occurring on that day. Providing no dates at all
-    /// will list only meetings for the current day.
+    /// Each bound accepts a bare date (2018-12-31), a bare time (meaning today at that
+    /// time), a full date-time, or a relative expression like `today`, `yesterday`, or
+    /// `-3` (three days ago). Provide both `since` and `until` to list meetings in that
+    /// range; provide only `since` to list everything from then until now; provide
+    /// neither to list today's meetings.
     #[structopt(name = "list", about = "List meetings for a given timeframe")]
     List {
+        /// e.g. 2018-12-31, 13:00, "2018-12-31 13:00", today, yesterday, -3
+        #[structopt(allow_hyphen_values = true)]
+        since: Option<String>,
+        /// e.g. 2018-12-31, 13:00, "2018-12-31 13:00", today, yesterday, -3
+        #[structopt(allow_hyphen_values = true)]
+        until: Option<String>,
+    },
+
+    /// Report meeting time for a week, broken down by day
+    ///
+    /// The target week is the current week plus `offset` weeks, so `-1` is last week
+    /// and `1` is next week. Each day's meetings are listed under a subtotal, followed
+    /// by a grand total for the week.
+    #[structopt(name = "week", about = "Report meetings for a week")]
+    Week {
+        /// Weeks relative to the current week, e.g. -1 for last week
+        #[structopt(default_value = "0", allow_hyphen_values = true)]
+        offset: i64,
+    },
+
+    /// Render meetings for a date range as an HTML calendar
+    ///
+    /// Builds a self-contained HTML page with one column per day and a colored block
+    /// for each meeting, positioned by its start hour and length. Takes an optional
+    /// start and end date: both give that range, a start alone gives that single day,
+    /// and neither gives today.
+    #[structopt(name = "html", about = "Emit an HTML calendar of meetings")]
+    Html {
         /// e.g. 2018-12-31
         start: Option<NaiveDate>,
         /// e.g. 2018-12-31
         end: Option<NaiveDate>,
+        /// Write the generated document to a file instead of stdout
+        #[structopt(long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Export meetings in a date range to CSV
+    ///
+    /// Accepts the same since/until bounds as `list`. Columns are local start time,
+    /// length in minutes, the computed end time, and the label, so the log can be opened in a
+    /// spreadsheet or handed off to another tool.
+    #[structopt(name = "export", about = "Export meetings to CSV")]
+    Export {
+        since: Option<String>,
+        until: Option<String>,
+        /// Write the CSV to a file instead of stdout
+        #[structopt(long = "output", parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Import meetings from a CSV file produced by `export`
+    ///
+    /// Each row is validated and appended to the log the same way `log` does, leaving
+    /// the canonical store intact.
+    #[structopt(name = "import", about = "Import meetings from CSV")]
+    Import {
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
     },
 }
 
+/// Settings read from `~/.meetingrc` at startup, overriding the hard-coded defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+struct Config {
+    /// Where the meeting log lives, in place of `~/.meetings`.
+    log_path: Option<PathBuf>,
+    /// strftime pattern used when displaying a meeting's timestamp.
+    datetime_format: Option<String>,
+    /// strftime pattern used when parsing bare dates.
+    date_format: Option<String>,
+}
+
+impl Config {
+    fn load() -> AppResult<Config> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    fn datetime_format(&self) -> &str {
+        self.datetime_format
+            .as_ref()
+            .map(String::as_str)
+            .unwrap_or("%F %R")
+    }
+
+    fn date_format(&self) -> &str {
+        self.date_format
+            .as_ref()
+            .map(String::as_str)
+            .unwrap_or("%F")
+    }
+}
+
+fn config_path() -> AppResult<PathBuf> {
+    use directories::UserDirs;
+
+    let path = UserDirs::new()
+        .ok_or("Unable to access user directories")?
+        .home_dir()
+        .join(".meetingrc");
+
+    Ok(path)
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct Meeting {
     timestamp: DateTime<Utc>,
     length: u32,
+    #[serde(default)]
+    label: Option<String>,
 }
 
 impl Meeting {
@@ -46,6 +172,7 @@ impl Meeting {
         Meeting {
             timestamp: Local::today().and_hms(start, 0, 0).with_timezone(&Utc),
             length,
+            label: None,
         }
     }
 
@@ -53,54 +180,307 @@ impl Meeting {
         let compare = self.timestamp.with_timezone(&Local).date();
         compare >= start && compare <= end
     }
-}
 
-impl Display for Meeting {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    /// Like `is_within_range`, but compares to-the-minute instants instead of whole
+    /// days, so `list` can filter on the sub-day precision a meeting already records.
+    fn is_within_instant_range(&self, start: DateTime<Local>, end: DateTime<Local>) -> bool {
+        let compare = self.timestamp.with_timezone(&Local);
+        compare >= start && compare <= end
+    }
+
+    /// Renders a record for display, honoring the configured `datetime_format`.
+    fn format(&self, config: &Config) -> String {
         let local = self.timestamp.with_timezone(&Local);
-        write!(
-            f,
-            "{}: {} minutes",
-            local.format("%F %R"),
-            self.length
-        )
+        match &self.label {
+            Some(label) => format!(
+                "{}: {} minutes ({})",
+                local.format(config.datetime_format()),
+                self.length,
+                label
+            ),
+            None => format!(
+                "{}: {} minutes",
+                local.format(config.datetime_format()),
+                self.length
+            ),
+        }
+    }
+}
+
+/// A meeting that has been opened by `start` but not yet closed by `stop`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct OpenMeeting {
+    timestamp: DateTime<Utc>,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+impl OpenMeeting {
+    fn close(self, end: DateTime<Utc>) -> Meeting {
+        // Clamp rather than let a backward clock adjustment (or a hand-edited log with
+        // a future open timestamp) wrap a negative duration into a huge `u32`.
+        Meeting {
+            timestamp: self.timestamp,
+            length: (end - self.timestamp).num_minutes().max(0) as u32,
+            label: self.label,
+        }
+    }
+}
+
+/// A single line of the log file, either an in-progress meeting or a finished one.
+///
+/// Closed is tried first so existing log lines (which always carry `length`) keep
+/// parsing the way they always have; only a line missing `length` falls through to
+/// the open variant.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Record {
+    Closed(Meeting),
+    Open(OpenMeeting),
+}
+
+impl Record {
+    fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Record::Closed(meeting) => meeting.timestamp,
+            Record::Open(open) => open.timestamp,
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        match self {
+            Record::Open(_) => true,
+            Record::Closed(_) => false,
+        }
+    }
+
+    fn into_closed(self) -> Option<Meeting> {
+        match self {
+            Record::Closed(meeting) => Some(meeting),
+            Record::Open(_) => None,
+        }
     }
 }
 
 fn main() -> AppResult<()> {
-    App::execute(Opt::from_args())
+    // Parse args first so `--help`/`--version` short-circuit before we touch config or
+    // the home directory at all, same as before `Config` existed.
+    let opt = Opt::from_args();
+    let config = Config::load()?;
+    App::execute(opt, &config)
 }
 
 struct App;
 
 impl App {
-    fn execute(opt: Opt) -> AppResult<()> {
+    fn execute(opt: Opt, config: &Config) -> AppResult<()> {
         match opt {
-            Opt::Log { start, length } => log(start, length),
-            Opt::List { start, end } => list(start, end),
+            Opt::Log { start, length } => log(start, length, config),
+            Opt::Start { label } => start(label, config),
+            Opt::Stop => stop(config),
+            Opt::List { since, until } => list(since, until, config),
+            Opt::Week { offset } => week(offset, config),
+            Opt::Html { start, end, output } => html(start, end, output, config),
+            Opt::Export {
+                since,
+                until,
+                output,
+            } => export(since, until, output, config),
+            Opt::Import { path } => import(path, config),
         }
     }
 }
 
-fn log(start: u32, length: u32) -> AppResult<()> {
+fn log(start: u32, length: u32, config: &Config) -> AppResult<()> {
+    append_meeting(&Meeting::today(start, length), config)
+}
+
+/// Appends a closed meeting to the log, used by `log` and `import` alike.
+fn append_meeting(meeting: &Meeting, config: &Config) -> AppResult<()> {
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    let path = log_path(config)?;
+    let mut log = OpenOptions::new().create(true).append(true).open(path)?;
+    serde_json::to_writer(&mut log, meeting)?;
+    log.write_all("\n".as_bytes())?;
+
+    Ok(())
+}
+
+fn start(label: Option<String>, config: &Config) -> AppResult<()> {
     use std::fs::OpenOptions;
     use std::io::Write;
 
-    let meeting = Meeting::today(start, length);
-    let path = log_path()?;
+    let record = Record::Open(OpenMeeting {
+        timestamp: Utc::now(),
+        label,
+    });
+    let path = log_path(config)?;
 
     let mut log = OpenOptions::new().create(true).append(true).open(path)?;
-    serde_json::to_writer(&mut log, &meeting)?;
+    serde_json::to_writer(&mut log, &record)?;
     log.write_all("\n".as_bytes())?;
 
     Ok(())
 }
 
-fn list(start: Option<NaiveDate>, end: Option<NaiveDate>) -> AppResult<()> {
+fn stop(config: &Config) -> AppResult<()> {
+    use std::fs::File;
+    use std::io::Write;
+
+    let path = log_path(config)?;
+    let content = fs::read_to_string(&path)?;
+
+    // Keep every line verbatim, parsed or not: a malformed or partially-written line
+    // (say, from a crash mid-write) is harmlessly ignored by readers today, and
+    // rewriting the file here must not be the thing that silently erases it.
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+
+    let open_index = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| parse_record(line).map(|record| (index, record)))
+        .filter(|(_, record)| record.is_open())
+        .max_by_key(|(_, record)| record.timestamp())
+        .map(|(index, _)| index)
+        .ok_or("No meeting is currently open")?;
+
+    let open = match parse_record(&lines[open_index]) {
+        Some(Record::Open(open)) => open,
+        _ => unreachable!("open_index was selected for being a parsed, open record"),
+    };
+
+    lines[open_index] = serde_json::to_string(&Record::Closed(open.close(Utc::now())))?;
+
+    let mut log = File::create(&path)?;
+    for line in &lines {
+        log.write_all(line.as_bytes())?;
+        log.write_all("\n".as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn list(since: Option<String>, until: Option<String>, config: &Config) -> AppResult<()> {
+    let (start, end) = bound_range(since, until, config)?;
+    let records = load_records(|x| x.is_within_instant_range(start, end), config)?;
+    print_records(records, config);
+    Ok(())
+}
+
+/// Resolves `list`'s optional `since`/`until` bounds to a concrete instant range: both
+/// given is that range, only `since` given is from then until now, neither given is
+/// today.
+fn bound_range(
+    since: Option<String>,
+    until: Option<String>,
+    config: &Config,
+) -> AppResult<(DateTime<Local>, DateTime<Local>)> {
+    match (since, until) {
+        (Some(since), Some(until)) => Ok((
+            parse_bound(&since, Bound::Since, config)?,
+            parse_bound(&until, Bound::Until, config)?,
+        )),
+        (Some(since), None) => Ok((parse_bound(&since, Bound::Since, config)?, Local::now())),
+        (None, None) => {
+            let start = Local::today().and_hms(0, 0, 0);
+            let end = start + Duration::days(1) - Duration::seconds(1);
+            Ok((start, end))
+        }
+
+        (None, Some(_)) => unreachable!(
+            "Structopt should not fill the second positional argument before the first."
+        ),
+    }
+}
+
+/// Which side of a `list` range a bound resolves, so a whole-day expression (a bare
+/// date, `today`, `yesterday`, or a relative offset) picks the right end of the day:
+/// midnight for `since`, the last second of the day for `until`. An explicit time of
+/// day in the input always wins regardless of which side it's on.
+#[derive(Clone, Copy)]
+enum Bound {
+    Since,
+    Until,
+}
+
+impl Bound {
+    fn day_time(self) -> NaiveTime {
+        match self {
+            Bound::Since => NaiveTime::from_hms(0, 0, 0),
+            Bound::Until => NaiveTime::from_hms(23, 59, 59),
+        }
+    }
+}
+
+/// Parses a `list` bound, trying progressively looser formats: a full date-time, a
+/// bare date, a bare time (today at that time), then the relative tokens
+/// `today`/`yesterday`, then a signed day offset from today (`-3` is three days ago,
+/// `3` three days ahead). Formats with no
+/// explicit time of day resolve to the start or end of that day per `bound`, so an
+/// inclusive-looking range like `2020-01-01 2020-01-05` covers all of the last day.
+fn parse_bound(s: &str, bound: Bound, config: &Config) -> AppResult<DateTime<Local>> {
+    let datetime_format = format!("{} %H:%M", config.date_format());
+    if let Ok(naive) = NaiveDateTime::parse_from_str(s, &datetime_format) {
+        return to_local_datetime(naive);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, config.date_format()) {
+        return to_local_datetime(date.and_time(bound.day_time()));
+    }
+
+    if let Ok(time) = NaiveTime::parse_from_str(s, "%H:%M") {
+        return to_local_datetime(Local::today().naive_local().and_time(time));
+    }
+
+    match s {
+        "today" => return to_local_datetime(Local::today().naive_local().and_time(bound.day_time())),
+        "yesterday" => {
+            let date = Local::today().naive_local() - Duration::days(1);
+            return to_local_datetime(date.and_time(bound.day_time()));
+        }
+        _ => {}
+    }
+
+    if let Ok(offset) = s.parse::<i64>() {
+        let date = Local::today().naive_local() + Duration::days(offset);
+        return to_local_datetime(date.and_time(bound.day_time()));
+    }
+
+    Err(format!(
+        "Unable to parse '{}' as a date, time, or relative expression",
+        s
+    ))?
+}
+
+fn to_local_datetime(naive: NaiveDateTime) -> AppResult<DateTime<Local>> {
+    use chrono::offset::{LocalResult, TimeZone};
+    use std::cmp;
+
+    match Local.from_local_datetime(&naive) {
+        LocalResult::None => Err("Invalid date/time")?,
+        LocalResult::Single(datetime) => Ok(datetime),
+        LocalResult::Ambiguous(left, right) => Ok(cmp::min(left, right)),
+    }
+}
+
+/// Resolves `html`'s optional start/end positionals to a concrete date range:
+/// both given is a range, one given is that single day, neither given is today.
+fn date_range(
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+) -> AppResult<(Date<Local>, Date<Local>)> {
     match (start, end) {
-        (Some(start), Some(end)) => list_range(start, end),
-        (Some(start), None) => list_date(start),
-        (None, None) => list_today(),
+        (Some(start), Some(end)) => Ok((to_local_date(start)?, to_local_date(end)?)),
+        (Some(start), None) => {
+            let date = to_local_date(start)?;
+            Ok((date, date))
+        }
+        (None, None) => {
+            let date = Local::today();
+            Ok((date, date))
+        }
 
         _ => unreachable!(
             "Structopt should not fill the second positional argument before the first."
@@ -108,33 +488,205 @@ fn list(start: Option<NaiveDate>, end: Option<NaiveDate>) -> AppResult<()> {
     }
 }
 
-fn list_range(start: NaiveDate, end: NaiveDate) -> AppResult<()> {
-    let start = to_local_date(start)?;
-    let end = to_local_date(end)?;
-    let records = load_records(|x| x.is_within_range(start, end))?;
-    print_records(records);
+fn week(offset: i64, config: &Config) -> AppResult<()> {
+    let monday = last_monday(offset);
+    let start = to_local_date(monday)?;
+    let end = to_local_date(monday + Duration::days(6))?;
+    let records = load_records(|x| x.is_within_range(start, end), config)?;
+
+    let mut by_day: BTreeMap<NaiveDate, Vec<Meeting>> = BTreeMap::new();
+    for record in records {
+        let date = record.timestamp.with_timezone(&Local).date().naive_local();
+        by_day.entry(date).or_insert_with(Vec::new).push(record);
+    }
+
+    let mut total_minutes = 0;
+    for day in 0..7 {
+        let date = monday + Duration::days(day);
+        println!("{}", date.format("%A, %F"));
+
+        let day_minutes = match by_day.get(&date) {
+            Some(records) => print_grouped_records(records, config),
+            None => 0,
+        };
+
+        println!("Subtotal: {:.1} hours\n", f64::from(day_minutes) / 60.0);
+        total_minutes += day_minutes;
+    }
+
+    println!("Total hours: {:.1}", f64::from(total_minutes) / 60.0);
     Ok(())
 }
 
-fn list_date(date: NaiveDate) -> AppResult<()> {
-    let date = to_local_date(date)?;
-    let records = load_records(|x| x.is_within_range(date, date))?;
-    print_records(records);
+fn html(
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    output: Option<PathBuf>,
+    config: &Config,
+) -> AppResult<()> {
+    let (start, end) = date_range(start, end)?;
+    let records = load_records(|x| x.is_within_range(start, end), config)?;
+    let document = render_html(start, end, &records, config);
+
+    match output {
+        Some(path) => fs::write(path, document)?,
+        None => println!("{}", document),
+    }
+
     Ok(())
 }
 
-fn list_today() -> AppResult<()> {
-    let date = Local::today();
-    let records = load_records(|x| x.is_within_range(date, date))?;
-    print_records(records);
+/// Renders meetings as a day-grid calendar: one `.day` column per date in
+/// `start..=end`, each with its own meetings absolutely-positioned by start hour and
+/// length in minutes.
+fn render_html(start: Date<Local>, end: Date<Local>, records: &[Meeting], config: &Config) -> String {
+    let mut total_minutes = 0;
+    let mut days = String::new();
+
+    let mut date = start;
+    while date <= end {
+        let mut blocks = String::new();
+
+        for record in records {
+            if record.timestamp.with_timezone(&Local).date() != date {
+                continue;
+            }
+
+            total_minutes += record.length;
+
+            let local = record.timestamp.with_timezone(&Local);
+            let top = local.hour() * 60 + local.minute();
+            let label = record.label.as_ref().map(String::as_str).unwrap_or("");
+
+            blocks.push_str(&format!(
+                "<div class=\"meeting\" style=\"top: {}px; height: {}px;\">{} &middot; {} min {}</div>\n",
+                top,
+                record.length,
+                escape_html(&local.format(config.datetime_format()).to_string()),
+                record.length,
+                escape_html(label),
+            ));
+        }
+
+        days.push_str(&format!(
+            "<div class=\"day\">\n<h2>{}</h2>\n{}</div>\n",
+            escape_html(&date.format("%A, %F").to_string()),
+            blocks
+        ));
+
+        date = date.succ();
+    }
+
+    format!(
+        "<!DOCTYPE html>\n\
+<html>\n\
+<head>\n\
+<meta charset=\"utf-8\">\n\
+<title>Meeting Calendar</title>\n\
+<style>\n\
+  body {{ font-family: sans-serif; }}\n\
+  .calendar {{ display: flex; gap: 8px; }}\n\
+  .day {{ position: relative; width: 200px; height: 1440px; border: 1px solid #ccc; }}\n\
+  .day h2 {{ position: absolute; top: -1.5em; left: 0; right: 0; margin: 0; font-size: 13px; white-space: nowrap; }}\n\
+  .meeting {{ position: absolute; left: 8px; right: 8px; background: #6fa8dc; color: #fff; padding: 2px 4px; font-size: 12px; overflow: hidden; }}\n\
+</style>\n\
+</head>\n\
+<body>\n\
+<div class=\"calendar\">\n\
+{}\
+</div>\n\
+<p>Total hours: {:.1}</p>\n\
+</body>\n\
+</html>\n",
+        days,
+        f64::from(total_minutes) / 60.0
+    )
+}
+
+/// Escapes text for safe interpolation into HTML produced by `render_html` — meeting
+/// labels are free-text user input and must not be able to break out of the markup.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A row of the CSV interchange format used by `export`/`import`.
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRecord {
+    start: String,
+    length: u32,
+    end: String,
+    label: Option<String>,
+}
+
+fn export(
+    since: Option<String>,
+    until: Option<String>,
+    output: Option<PathBuf>,
+    config: &Config,
+) -> AppResult<()> {
+    use std::io;
+
+    let (start, end) = bound_range(since, until, config)?;
+    let records = load_records(|x| x.is_within_instant_range(start, end), config)?;
+
+    let mut writer = match &output {
+        Some(path) => csv::Writer::from_path(path)?,
+        None => csv::Writer::from_writer(io::stdout()),
+    };
+
+    for record in &records {
+        let local_start = record.timestamp.with_timezone(&Local);
+        let local_end = local_start + Duration::minutes(i64::from(record.length));
+
+        writer.serialize(CsvRecord {
+            start: local_start.format(config.datetime_format()).to_string(),
+            length: record.length,
+            end: local_end.format(config.datetime_format()).to_string(),
+            label: record.label.clone(),
+        })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn import(path: PathBuf, config: &Config) -> AppResult<()> {
+    let mut reader = csv::Reader::from_path(path)?;
+
+    for row in reader.deserialize() {
+        let row: CsvRecord = row?;
+        let naive = NaiveDateTime::parse_from_str(&row.start, config.datetime_format())?;
+        let timestamp = to_local_datetime(naive)?.with_timezone(&Utc);
+
+        append_meeting(
+            &Meeting {
+                timestamp,
+                length: row.length,
+                label: row.label,
+            },
+            config,
+        )?;
+    }
+
     Ok(())
 }
 
-fn load_records(f: impl FnMut(&Meeting) -> bool) -> AppResult<Vec<Meeting>> {
-    let content = fs::read_to_string(&log_path()?)?;
+/// The Monday of the week `offset` weeks from the current week.
+fn last_monday(offset: i64) -> NaiveDate {
+    let today = Local::today().naive_local();
+    let monday = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+    monday + Duration::days(offset * 7)
+}
+
+fn load_records(f: impl FnMut(&Meeting) -> bool, config: &Config) -> AppResult<Vec<Meeting>> {
+    let content = fs::read_to_string(&log_path(config)?)?;
     let mut records: Vec<_> = content
         .lines()
-        .filter_map(parse_meeting)
+        .filter_map(parse_record)
+        .filter_map(Record::into_closed)
         .filter(f)
         .collect();
 
@@ -142,21 +694,37 @@ fn load_records(f: impl FnMut(&Meeting) -> bool) -> AppResult<Vec<Meeting>> {
     Ok(records)
 }
 
-fn print_records(records: impl IntoIterator<Item = Meeting>) {
+fn print_records(records: impl IntoIterator<Item = Meeting>, config: &Config) {
     let mut minutes = 0;
     for record in records {
         minutes += record.length;
-        println!("{}", record);
+        println!("{}", record.format(config));
     }
 
     println!("Total hours: {:.1}", f64::from(minutes) / 60.0);
 }
 
-fn parse_meeting(s: &str) -> Option<Meeting> {
+/// Like `print_records`, but prints only the entries (no total line) and returns the
+/// number of minutes logged, so a caller can fold several days into its own total.
+fn print_grouped_records(records: &[Meeting], config: &Config) -> u32 {
+    let mut minutes = 0;
+    for record in records {
+        minutes += record.length;
+        println!("{}", record.format(config));
+    }
+
+    minutes
+}
+
+fn parse_record(s: &str) -> Option<Record> {
     serde_json::from_str(s).ok()
 }
 
-fn log_path() -> AppResult<PathBuf> {
+fn log_path(config: &Config) -> AppResult<PathBuf> {
+    if let Some(path) = &config.log_path {
+        return Ok(path.clone());
+    }
+
     use directories::UserDirs;
 
     let path = UserDirs::new()
@@ -178,3 +746,54 @@ fn to_local_date(date: NaiveDate) -> AppResult<Date<Local>> {
         LocalResult::Ambiguous(left, right) => Ok(cmp::min(left, right)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_date_since_is_midnight() {
+        let bound = parse_bound("2020-01-01", Bound::Since, &Config::default()).unwrap();
+        assert_eq!(
+            bound.naive_local(),
+            NaiveDate::from_ymd(2020, 1, 1).and_hms(0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn bare_date_until_is_end_of_day() {
+        let bound = parse_bound("2020-01-01", Bound::Until, &Config::default()).unwrap();
+        assert_eq!(
+            bound.naive_local(),
+            NaiveDate::from_ymd(2020, 1, 1).and_hms(23, 59, 59)
+        );
+    }
+
+    #[test]
+    fn today_until_is_end_of_day() {
+        let bound = parse_bound("today", Bound::Until, &Config::default()).unwrap();
+        let today = Local::today().naive_local();
+        assert_eq!(bound.naive_local(), today.and_hms(23, 59, 59));
+    }
+
+    #[test]
+    fn yesterday_since_is_midnight() {
+        let bound = parse_bound("yesterday", Bound::Since, &Config::default()).unwrap();
+        let yesterday = Local::today().naive_local() - Duration::days(1);
+        assert_eq!(bound.naive_local(), yesterday.and_hms(0, 0, 0));
+    }
+
+    #[test]
+    fn relative_offset_until_is_end_of_day() {
+        let bound = parse_bound("-3", Bound::Until, &Config::default()).unwrap();
+        let date = Local::today().naive_local() - Duration::days(3);
+        assert_eq!(bound.naive_local(), date.and_hms(23, 59, 59));
+    }
+
+    #[test]
+    fn bare_time_ignores_bound_side() {
+        let bound = parse_bound("13:00", Bound::Until, &Config::default()).unwrap();
+        let expected = Local::today().naive_local().and_hms(13, 0, 0);
+        assert_eq!(bound.naive_local(), expected);
+    }
+}